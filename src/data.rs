@@ -6,6 +6,12 @@ use std::{
 use chrono::{Datelike, Timelike};
 use indexmap::IndexMap;
 
+mod enums;
+mod validation;
+
+pub use enums::{Award, Band, Mode};
+pub use validation::ValidationError;
+
 #[derive(Debug)]
 pub struct SerializeError {
     pub message: String,
@@ -18,30 +24,86 @@ impl Display for SerializeError {
     }
 }
 
+/// Whether an [`AdifType::Time`] was written (or should be written) with
+/// seconds included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimePrecision {
+    /// 4-digit `HHMM` form, e.g. `2330`
+    HourMinute,
+
+    /// 6-digit `HHMMSS` form, e.g. `233000`
+    HourMinuteSecond,
+}
+
 /// Supported datatypes for representing ADIF data
 #[derive(Debug, Clone, PartialEq)]
 pub enum AdifType {
-    /// Basic string type
+    /// Basic string type. Must be ASCII, and cannot contain linebreaks
     Str(String),
 
+    /// A `String` that allows UTF-8 encoded international characters, but
+    /// (like `Str`) still cannot contain linebreaks
+    IntlString(String),
+
+    /// A `Str` that is permitted to contain embedded linebreaks
+    MultilineString(String),
+
+    /// A `MultilineString` that also allows UTF-8 encoded international
+    /// characters
+    IntlMultilineString(String),
+
     /// Basic boolean type
     Boolean(bool),
 
-    /// Basic number type
+    /// Basic number type. See also [`AdifType::Integer`] for whole numbers
+    /// that should not round-trip through an `f64`
     Number(f64),
 
+    /// A whole number. ADIF shares the `N` type indicator between this and
+    /// [`AdifType::Number`] on the wire, but keeping the two separate in
+    /// memory means an `<...:N>` integer never has to pass through `f64` to
+    /// be constructed or read back out
+    Integer(i64),
+
     /// 8 Digits representing a UTC date in `YYYYMMDD` format, where
     ///  - YYYY is a 4-Digit year specifier, where 1930 <= YYYY
     ///  - MM is a 2-Digit month specifier, where 1 <= MM <= 12
     ///  - DD is a 2-Digit day specifier, where 1 <= DD <= DaysInMonth(MM)
+    ///
+    /// All ADIF dates are in UTC
     Date(chrono::NaiveDate),
 
-    /// 6 Digits representing a UTC time in HHMMSS format
-    /// or 4 Digits representing a time in HHMM format, where:
+    /// A UTC time, serialized as 6 digits in `HHMMSS` format or 4 digits in
+    /// `HHMM` format depending on its [`TimePrecision`], where:
     ///  - HH is a 2-Digit hour specifier, where 0 <= HH <= 23
     ///  - MM is a 2-Digit minute specifier, where 0 <= MM <= 59
     ///  - SS is a 2-Digit second specifier, where 0 <= SS <= 59
-    Time(chrono::NaiveTime),
+    ///
+    /// The precision must be tracked alongside the time itself: a bare
+    /// `chrono::NaiveTime` can't tell a `23:03:00` that was truncated to
+    /// `2303` on the wire from one that genuinely had zero seconds, so
+    /// serializing it back out would silently guess
+    Time(chrono::NaiveTime, TimePrecision),
+
+    /// A latitude, stored as signed decimal degrees and serialized in
+    /// ADIF's `XDDD MM.MMM` form (e.g. `N045 30.000`). Positive values
+    /// serialize with the `N` direction letter, negative values with `S`.
+    /// ADIF shares the `L` type indicator between this and
+    /// [`AdifType::Longitude`]; they're kept separate in memory so each can
+    /// always round-trip through its own pair of direction letters
+    Latitude(f64),
+
+    /// A longitude, stored as signed decimal degrees and serialized in
+    /// ADIF's `XDDD MM.MMM` form (e.g. `W122 30.000`). Positive values
+    /// serialize with the `E` direction letter, negative values with `W`
+    Longitude(f64),
+
+    /// A Maidenhead grid square locator, e.g. `FN20` or `FN20qi12`. ADIF has
+    /// no wire-level type indicator for grid squares, so parsing a plain
+    /// `<GRIDSQUARE:...>` field will still produce an [`AdifType::Str`] --
+    /// this variant exists so code constructing records can get maidenhead
+    /// length validation for free
+    GridSquare(String),
 }
 
 impl AdifType {
@@ -49,10 +111,17 @@ impl AdifType {
     pub fn get_data_type_indicator(&self) -> Option<char> {
         match self {
             AdifType::Str(_) => None,
+            AdifType::IntlString(_) => Some('I'),
+            AdifType::MultilineString(_) => Some('M'),
+            AdifType::IntlMultilineString(_) => Some('G'),
             AdifType::Boolean(_) => Some('B'),
             AdifType::Number(_) => Some('N'),
+            AdifType::Integer(_) => Some('N'),
             AdifType::Date(_) => Some('D'),
-            AdifType::Time(_) => Some('T'),
+            AdifType::Time(_, _) => Some('T'),
+            AdifType::Latitude(_) => Some('L'),
+            AdifType::Longitude(_) => Some('L'),
+            AdifType::GridSquare(_) => None,
         }
     }
 
@@ -77,12 +146,36 @@ impl AdifType {
 
                 Ok(val.to_string())
             }
+            AdifType::IntlString(val) => {
+                // UTF-8 is permitted here, but linebreaks still are not
+                if val.contains('\n') {
+                    return Err(SerializeError {
+                        message: "String cannot contain linebreaks".to_string(),
+                        offender: val.to_string(),
+                    });
+                }
+
+                Ok(val.to_string())
+            }
+            AdifType::MultilineString(val) => {
+                // Linebreaks are permitted here, but the value must be ASCII
+                if !val.is_ascii() {
+                    return Err(SerializeError {
+                        message: "String must be ASCII".to_string(),
+                        offender: val.to_string(),
+                    });
+                }
+
+                Ok(val.to_string())
+            }
+            AdifType::IntlMultilineString(val) => Ok(val.to_string()),
             AdifType::Boolean(val) => Ok(match val {
                 true => "Y",
                 false => "N",
             }
             .to_string()),
             AdifType::Number(val) => Ok(val.to_string()),
+            AdifType::Integer(val) => Ok(val.to_string()),
             AdifType::Date(val) => {
                 // Date must be after 1929
                 if val.year() < 1930 {
@@ -94,12 +187,24 @@ impl AdifType {
 
                 Ok(format!("{}{:02}{:02}", val.year(), val.month(), val.day()))
             }
-            AdifType::Time(val) => Ok(format!(
-                "{:02}{:02}{:02}",
-                val.hour(),
-                val.minute(),
-                val.second()
-            )),
+            AdifType::Time(val, precision) => Ok(match precision {
+                TimePrecision::HourMinute => format!("{:02}{:02}", val.hour(), val.minute()),
+                TimePrecision::HourMinuteSecond => {
+                    format!("{:02}{:02}{:02}", val.hour(), val.minute(), val.second())
+                }
+            }),
+            AdifType::Latitude(val) => Ok(format_location(*val, 'N', 'S')),
+            AdifType::Longitude(val) => Ok(format_location(*val, 'E', 'W')),
+            AdifType::GridSquare(val) => {
+                if ![2, 4, 6, 8].contains(&val.len()) {
+                    return Err(SerializeError {
+                        message: "Grid square must be 2, 4, 6, or 8 characters long".to_string(),
+                        offender: val.to_string(),
+                    });
+                }
+
+                Ok(val.to_string())
+            }
         };
         let value: &str = &(value?);
 
@@ -115,6 +220,20 @@ impl AdifType {
             value
         ))
     }
+
+    /// Borrow the inner value as a string slice, for the string-like
+    /// variants. Returns `None` for `Boolean`, `Number`, `Integer`, `Date`,
+    /// `Time`, `Latitude`, and `Longitude`
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AdifType::Str(val)
+            | AdifType::IntlString(val)
+            | AdifType::MultilineString(val)
+            | AdifType::IntlMultilineString(val)
+            | AdifType::GridSquare(val) => Some(val),
+            _ => None,
+        }
+    }
 }
 
 impl Display for AdifType {
@@ -123,6 +242,26 @@ impl Display for AdifType {
     }
 }
 
+/// Format signed decimal degrees into ADIF's `XDDD MM.MMM` location form,
+/// using `positive`/`negative` as the direction letters for the two signs.
+/// Minutes are rounded to 3 decimal places before the overflow check, so
+/// a value like `45.999992` (whose minutes component is `59.99951...`, a
+/// `{:06.3}`-formatted `"60.000"`) carries the extra minute into `degrees`
+/// instead of printing the spec-invalid `"N045 60.000"`
+fn format_location(val: f64, positive: char, negative: char) -> String {
+    let direction = if val >= 0.0 { positive } else { negative };
+    let abs = val.abs();
+    let mut degrees = abs.trunc() as u32;
+    let mut minutes = (abs.fract() * 60.0 * 1000.0).round() / 1000.0;
+
+    if minutes >= 60.0 {
+        minutes -= 60.0;
+        degrees += 1;
+    }
+
+    format!("{}{:03} {:06.3}", direction, degrees, minutes)
+}
+
 /// A single ADIF record, consisting of many values
 #[derive(Debug, Clone, PartialEq)]
 pub struct AdifRecord(IndexMap<String, AdifType>);
@@ -263,6 +402,277 @@ impl AdifFile {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{AdifFile, AdifHeader, AdifRecord, AdifType, TimePrecision};
+
+    /// Mirrors [`TimePrecision`] for JSON purposes -- kept as its own type
+    /// so the derive can pick the wire names, rather than exposing
+    /// `TimePrecision`'s Rust variant names directly
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum TimePrecisionShadow {
+        HourMinute,
+        HourMinuteSecond,
+    }
+
+    impl From<TimePrecision> for TimePrecisionShadow {
+        fn from(value: TimePrecision) -> Self {
+            match value {
+                TimePrecision::HourMinute => TimePrecisionShadow::HourMinute,
+                TimePrecision::HourMinuteSecond => TimePrecisionShadow::HourMinuteSecond,
+            }
+        }
+    }
+
+    impl From<TimePrecisionShadow> for TimePrecision {
+        fn from(value: TimePrecisionShadow) -> Self {
+            match value {
+                TimePrecisionShadow::HourMinute => TimePrecision::HourMinute,
+                TimePrecisionShadow::HourMinuteSecond => TimePrecision::HourMinuteSecond,
+            }
+        }
+    }
+
+    /// A tagged mirror of [`AdifType`] used purely as a JSON wire format.
+    /// Every variant is kept distinct on the wire (`{"type": "intl_string",
+    /// "value": "..."}`) rather than collapsing to the JSON scalar it
+    /// resembles, so that round-tripping through [`AdifFile::to_json`] and
+    /// [`AdifFile::from_json`] always reconstructs the exact variant that
+    /// was serialized -- an earlier, untagged encoding folded `IntlString`,
+    /// `MultilineString`, `IntlMultilineString`, and `GridSquare` all into
+    /// `Str`, which could turn a round-tripped accented or multiline value
+    /// into one `AdifType::serialize` then rejected
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "type", content = "value", rename_all = "snake_case")]
+    enum AdifTypeShadow {
+        Str(String),
+        IntlString(String),
+        MultilineString(String),
+        IntlMultilineString(String),
+        Boolean(bool),
+        Number(f64),
+        Integer(i64),
+        Date(String),
+        Time(String, TimePrecisionShadow),
+        Latitude(f64),
+        Longitude(f64),
+        GridSquare(String),
+    }
+
+    impl From<&AdifType> for AdifTypeShadow {
+        fn from(value: &AdifType) -> Self {
+            match value {
+                AdifType::Str(val) => AdifTypeShadow::Str(val.clone()),
+                AdifType::IntlString(val) => AdifTypeShadow::IntlString(val.clone()),
+                AdifType::MultilineString(val) => AdifTypeShadow::MultilineString(val.clone()),
+                AdifType::IntlMultilineString(val) => {
+                    AdifTypeShadow::IntlMultilineString(val.clone())
+                }
+                AdifType::Boolean(val) => AdifTypeShadow::Boolean(*val),
+                AdifType::Number(val) => AdifTypeShadow::Number(*val),
+                AdifType::Integer(val) => AdifTypeShadow::Integer(*val),
+                AdifType::Date(val) => AdifTypeShadow::Date(val.format("%Y-%m-%d").to_string()),
+                AdifType::Time(val, precision) => {
+                    AdifTypeShadow::Time(val.format("%H:%M:%S").to_string(), (*precision).into())
+                }
+                AdifType::Latitude(val) => AdifTypeShadow::Latitude(*val),
+                AdifType::Longitude(val) => AdifTypeShadow::Longitude(*val),
+                AdifType::GridSquare(val) => AdifTypeShadow::GridSquare(val.clone()),
+            }
+        }
+    }
+
+    impl TryFrom<AdifTypeShadow> for AdifType {
+        type Error = String;
+
+        fn try_from(value: AdifTypeShadow) -> Result<Self, Self::Error> {
+            Ok(match value {
+                AdifTypeShadow::Str(val) => AdifType::Str(val),
+                AdifTypeShadow::IntlString(val) => AdifType::IntlString(val),
+                AdifTypeShadow::MultilineString(val) => AdifType::MultilineString(val),
+                AdifTypeShadow::IntlMultilineString(val) => AdifType::IntlMultilineString(val),
+                AdifTypeShadow::Boolean(val) => AdifType::Boolean(val),
+                AdifTypeShadow::Number(val) => AdifType::Number(val),
+                AdifTypeShadow::Integer(val) => AdifType::Integer(val),
+                AdifTypeShadow::Date(val) => AdifType::Date(
+                    chrono::NaiveDate::parse_from_str(&val, "%Y-%m-%d")
+                        .map_err(|err| format!("`{}` is not a valid date: {}", val, err))?,
+                ),
+                AdifTypeShadow::Time(val, precision) => AdifType::Time(
+                    chrono::NaiveTime::parse_from_str(&val, "%H:%M:%S")
+                        .map_err(|err| format!("`{}` is not a valid time: {}", val, err))?,
+                    precision.into(),
+                ),
+                AdifTypeShadow::Latitude(val) => AdifType::Latitude(val),
+                AdifTypeShadow::Longitude(val) => AdifType::Longitude(val),
+                AdifTypeShadow::GridSquare(val) => AdifType::GridSquare(val),
+            })
+        }
+    }
+
+    impl Serialize for AdifType {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            AdifTypeShadow::from(self).serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for AdifType {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            AdifTypeShadow::deserialize(deserializer)
+                .and_then(|shadow| AdifType::try_from(shadow).map_err(D::Error::custom))
+        }
+    }
+
+    // `AdifRecord` and `AdifHeader` are thin wrappers around an `IndexMap`,
+    // so delegate straight to its `Serialize`/`Deserialize` impl. IndexMap
+    // preserves insertion order when it round-trips through JSON.
+    impl Serialize for AdifRecord {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            self.0.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for AdifRecord {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Deserialize::deserialize(deserializer).map(Self)
+        }
+    }
+
+    impl Serialize for AdifHeader {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            self.0.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for AdifHeader {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Deserialize::deserialize(deserializer).map(Self)
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct AdifFileShadow {
+        header: AdifHeader,
+        body: Vec<AdifRecord>,
+    }
+
+    impl Serialize for AdifFile {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            AdifFileShadow {
+                header: self.header.clone(),
+                body: self.body.clone(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for AdifFile {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let shadow = AdifFileShadow::deserialize(deserializer)?;
+            Ok(AdifFile {
+                header: shadow.header,
+                body: shadow.body,
+            })
+        }
+    }
+
+    impl AdifFile {
+        /// Serialize into a JSON string, for piping logging data into web
+        /// tooling or other parts of the serde ecosystem
+        pub fn to_json(&self) -> serde_json::Result<String> {
+            serde_json::to_string(self)
+        }
+
+        /// Parse an `AdifFile` back out of a JSON string produced by
+        /// [`AdifFile::to_json`]
+        pub fn from_json(data: &str) -> serde_json::Result<Self> {
+            serde_json::from_str(data)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod json_tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    pub fn test_json_round_trip_preserves_intl_and_multiline_variants() {
+        let file = AdifFile {
+            header: IndexMap::<String, AdifType>::new().into(),
+            body: vec![indexmap! {
+                "NAME" => AdifType::IntlString("Évan".to_string()),
+                "NOTES" => AdifType::MultilineString("line one\nline two".to_string()),
+                "COMMENT" => AdifType::IntlMultilineString("Évan\nPratten".to_string()),
+                "GRIDSQUARE" => AdifType::GridSquare("FN20".to_string()),
+            }
+            .into()],
+        };
+
+        let json = file.to_json().unwrap();
+        let round_tripped = AdifFile::from_json(&json).unwrap();
+
+        assert_eq!(round_tripped, file);
+        // The whole point: the round-tripped values must still serialize,
+        // which an `AdifType::Str` holding non-ASCII or embedded linebreaks
+        // would not
+        round_tripped.body[0].serialize().unwrap();
+    }
+
+    #[test]
+    pub fn test_json_round_trip_preserves_latitude_and_longitude() {
+        let file = AdifFile {
+            header: IndexMap::<String, AdifType>::new().into(),
+            body: vec![indexmap! {
+                "LAT" => AdifType::Latitude(-45.5),
+                "LON" => AdifType::Longitude(-122.5),
+            }
+            .into()],
+        };
+
+        let json = file.to_json().unwrap();
+        let round_tripped = AdifFile::from_json(&json).unwrap();
+
+        assert_eq!(round_tripped, file);
+        assert_eq!(
+            round_tripped.body[0]
+                .get("LAT")
+                .unwrap()
+                .serialize("LAT")
+                .unwrap(),
+            "<LAT:11:L>S045 30.000"
+        );
+    }
+}
+
 #[cfg(test)]
 mod types_tests {
     use chrono::{NaiveDate, NaiveTime};
@@ -323,11 +733,118 @@ mod types_tests {
     #[test]
     pub fn test_ser_time() {
         assert_eq!(
-            AdifType::Time(NaiveTime::from_hms_opt(23, 2, 5).unwrap())
+            AdifType::Time(
+                NaiveTime::from_hms_opt(23, 2, 5).unwrap(),
+                TimePrecision::HourMinuteSecond
+            )
+            .serialize("test")
+            .unwrap(),
+            "<TEST:6:T>230205"
+        );
+    }
+
+    #[test]
+    pub fn test_ser_time_hour_minute() {
+        assert_eq!(
+            AdifType::Time(
+                NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+                TimePrecision::HourMinute
+            )
+            .serialize("test")
+            .unwrap(),
+            "<TEST:4:T>2330"
+        );
+    }
+
+    #[test]
+    pub fn test_ser_intl_string() {
+        assert_eq!(
+            AdifType::IntlString("Évan".to_string())
                 .serialize("test")
                 .unwrap(),
-            "<TEST:6:T>230205"
+            "<TEST:5:I>Évan"
         );
+        assert!(AdifType::IntlString("line\nbreak".to_string())
+            .serialize("test")
+            .is_err());
+    }
+
+    #[test]
+    pub fn test_ser_multiline_string() {
+        assert_eq!(
+            AdifType::MultilineString("line one\nline two".to_string())
+                .serialize("test")
+                .unwrap(),
+            "<TEST:17:M>line one\nline two"
+        );
+        assert!(AdifType::MultilineString("non-ascii é".to_string())
+            .serialize("test")
+            .is_err());
+    }
+
+    #[test]
+    pub fn test_ser_intl_multiline_string() {
+        assert_eq!(
+            AdifType::IntlMultilineString("Évan\nPratten".to_string())
+                .serialize("test")
+                .unwrap(),
+            "<TEST:13:G>Évan\nPratten"
+        );
+    }
+
+    #[test]
+    pub fn test_ser_integer() {
+        assert_eq!(
+            AdifType::Integer(-42).serialize("test").unwrap(),
+            "<TEST:3:N>-42"
+        );
+    }
+
+    #[test]
+    pub fn test_ser_latitude() {
+        assert_eq!(
+            AdifType::Latitude(45.5).serialize("test").unwrap(),
+            "<TEST:11:L>N045 30.000"
+        );
+        assert_eq!(
+            AdifType::Latitude(-45.5).serialize("test").unwrap(),
+            "<TEST:11:L>S045 30.000"
+        );
+    }
+
+    #[test]
+    pub fn test_ser_longitude() {
+        assert_eq!(
+            AdifType::Longitude(122.5).serialize("test").unwrap(),
+            "<TEST:11:L>E122 30.000"
+        );
+        assert_eq!(
+            AdifType::Longitude(-122.5).serialize("test").unwrap(),
+            "<TEST:11:L>W122 30.000"
+        );
+    }
+
+    #[test]
+    pub fn test_ser_latitude_minutes_rounding_carries_into_degrees() {
+        // fract() * 60 here is 59.99951999..., which `{:06.3}` alone would
+        // round up to the spec-invalid "60.000"
+        assert_eq!(
+            AdifType::Latitude(45.999992).serialize("test").unwrap(),
+            "<TEST:11:L>N046 00.000"
+        );
+    }
+
+    #[test]
+    pub fn test_ser_grid_square() {
+        assert_eq!(
+            AdifType::GridSquare("FN20".to_string())
+                .serialize("test")
+                .unwrap(),
+            "<TEST:4>FN20"
+        );
+        assert!(AdifType::GridSquare("FN2".to_string())
+            .serialize("test")
+            .is_err());
     }
 }
 
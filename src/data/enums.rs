@@ -1,3 +1,5 @@
+use std::{fmt::Display, str::FromStr};
+
 /// All possible ADIF awards
 ///
 /// See: https://www.adif.org/312/ADIF_312.htm#Award_Enumeration
@@ -34,3 +36,366 @@ pub enum Award {
     USACA,
     VUCC,
 }
+
+impl Award {
+    /// All defined `Award` variants, in declaration order
+    pub const ALL: &'static [Award] = &[
+        Award::AJA,
+        Award::CQDX,
+        Award::CQDXFIELD,
+        Award::CQWAZ_MIXED,
+        Award::CQWAZ_CW,
+        Award::CQWAZ_PHONE,
+        Award::CQWAZ_RTTY,
+        Award::CQWAZ_160m,
+        Award::CQWPX,
+        Award::DARC_DOK,
+        Award::DXCC,
+        Award::DXCC_MIXED,
+        Award::DXCC_CW,
+        Award::DXCC_PHONE,
+        Award::DXCC_RTTY,
+        Award::IOTA,
+        Award::JCC,
+        Award::JCG,
+        Award::MARATHON,
+        Award::RDA,
+        Award::WAB,
+        Award::WAC,
+        Award::WAE,
+        Award::WAIP,
+        Award::WAJA,
+        Award::WAS,
+        Award::WAZ,
+        Award::USACA,
+        Award::VUCC,
+    ];
+}
+
+impl Display for Award {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Error returned when a string does not name a known [`Award`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownAwardError(pub String);
+
+impl Display for UnknownAwardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` is not a recognized ADIF AWARD value", self.0)
+    }
+}
+
+impl FromStr for Award {
+    type Err = UnknownAwardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Case-insensitive on both sides: `Award::CQWAZ_160m`'s `Display`
+        // (derived from the variant name) contains a lowercase `m`, so
+        // comparing against an uppercased `s` alone would mean that variant
+        // could never parse from any input, including its own rendering
+        Award::ALL
+            .iter()
+            .copied()
+            .find(|award| award.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownAwardError(s.to_string()))
+    }
+}
+
+/// The legal values of the ADIF `BAND` enumeration
+///
+/// See: https://www.adif.org/312/ADIF_312.htm#Band_Enumeration
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    Band2190m,
+    Band630m,
+    Band560m,
+    Band160m,
+    Band80m,
+    Band60m,
+    Band40m,
+    Band30m,
+    Band20m,
+    Band17m,
+    Band15m,
+    Band12m,
+    Band10m,
+    Band8m,
+    Band6m,
+    Band5m,
+    Band4m,
+    Band2m,
+    Band1_25m,
+    Band70cm,
+    Band33cm,
+    Band23cm,
+    Band13cm,
+    Band9cm,
+    Band6cm,
+    Band3cm,
+    Band1_25cm,
+    Band6mm,
+    Band4mm,
+    Band2_5mm,
+    Band2mm,
+    Band1mm,
+}
+
+impl Band {
+    /// All defined `Band` variants paired with their wire representation,
+    /// in declaration order. A pairing table (rather than deriving
+    /// `Display`/`FromStr` from the variant name, as [`Award`] does) is
+    /// needed here because the wire values -- `2190m`, `1.25m`, `70cm` --
+    /// aren't valid Rust identifiers
+    pub const ALL: &'static [(Band, &'static str)] = &[
+        (Band::Band2190m, "2190m"),
+        (Band::Band630m, "630m"),
+        (Band::Band560m, "560m"),
+        (Band::Band160m, "160m"),
+        (Band::Band80m, "80m"),
+        (Band::Band60m, "60m"),
+        (Band::Band40m, "40m"),
+        (Band::Band30m, "30m"),
+        (Band::Band20m, "20m"),
+        (Band::Band17m, "17m"),
+        (Band::Band15m, "15m"),
+        (Band::Band12m, "12m"),
+        (Band::Band10m, "10m"),
+        (Band::Band8m, "8m"),
+        (Band::Band6m, "6m"),
+        (Band::Band5m, "5m"),
+        (Band::Band4m, "4m"),
+        (Band::Band2m, "2m"),
+        (Band::Band1_25m, "1.25m"),
+        (Band::Band70cm, "70cm"),
+        (Band::Band33cm, "33cm"),
+        (Band::Band23cm, "23cm"),
+        (Band::Band13cm, "13cm"),
+        (Band::Band9cm, "9cm"),
+        (Band::Band6cm, "6cm"),
+        (Band::Band3cm, "3cm"),
+        (Band::Band1_25cm, "1.25cm"),
+        (Band::Band6mm, "6mm"),
+        (Band::Band4mm, "4mm"),
+        (Band::Band2_5mm, "2.5mm"),
+        (Band::Band2mm, "2mm"),
+        (Band::Band1mm, "1mm"),
+    ];
+}
+
+impl Display for Band {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (_, wire) = Band::ALL.iter().find(|(band, _)| band == self).unwrap();
+        write!(f, "{}", wire)
+    }
+}
+
+/// Error returned when a string does not name a known [`Band`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownBandError(pub String);
+
+impl Display for UnknownBandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` is not a recognized ADIF BAND value", self.0)
+    }
+}
+
+impl FromStr for Band {
+    type Err = UnknownBandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Band::ALL
+            .iter()
+            .find(|(_, wire)| wire.eq_ignore_ascii_case(s))
+            .map(|(band, _)| *band)
+            .ok_or_else(|| UnknownBandError(s.to_string()))
+    }
+}
+
+/// A representative subset of the legal values of the ADIF `MODE`
+/// enumeration. `SUBMODE` values are not validated, since the legal set
+/// depends on the paired `MODE`
+///
+/// See: https://www.adif.org/312/ADIF_312.htm#Mode_Enumeration
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    AM,
+    ARDOP,
+    ATV,
+    C4FM,
+    CHIP,
+    CLO,
+    CONTESTI,
+    CW,
+    DIGITALVOICE,
+    DOMINO,
+    DSTAR,
+    FAX,
+    FM,
+    FSK441,
+    FT8,
+    HELL,
+    ISCAT,
+    JT4,
+    JT6M,
+    JT9,
+    JT44,
+    JT65,
+    MFSK,
+    MSK144,
+    MT63,
+    OLIVIA,
+    OPERA,
+    PAC,
+    PAX,
+    PKT,
+    PSK,
+    PSK2K,
+    Q15,
+    QRA64,
+    ROS,
+    RTTY,
+    RTTYM,
+    SSB,
+    SSTV,
+    THOR,
+    THROB,
+    TOR,
+    V4,
+    VOI,
+    WINMOR,
+    WSPR,
+}
+
+impl Mode {
+    /// All defined `Mode` variants, in declaration order
+    pub const ALL: &'static [Mode] = &[
+        Mode::AM,
+        Mode::ARDOP,
+        Mode::ATV,
+        Mode::C4FM,
+        Mode::CHIP,
+        Mode::CLO,
+        Mode::CONTESTI,
+        Mode::CW,
+        Mode::DIGITALVOICE,
+        Mode::DOMINO,
+        Mode::DSTAR,
+        Mode::FAX,
+        Mode::FM,
+        Mode::FSK441,
+        Mode::FT8,
+        Mode::HELL,
+        Mode::ISCAT,
+        Mode::JT4,
+        Mode::JT6M,
+        Mode::JT9,
+        Mode::JT44,
+        Mode::JT65,
+        Mode::MFSK,
+        Mode::MSK144,
+        Mode::MT63,
+        Mode::OLIVIA,
+        Mode::OPERA,
+        Mode::PAC,
+        Mode::PAX,
+        Mode::PKT,
+        Mode::PSK,
+        Mode::PSK2K,
+        Mode::Q15,
+        Mode::QRA64,
+        Mode::ROS,
+        Mode::RTTY,
+        Mode::RTTYM,
+        Mode::SSB,
+        Mode::SSTV,
+        Mode::THOR,
+        Mode::THROB,
+        Mode::TOR,
+        Mode::V4,
+        Mode::VOI,
+        Mode::WINMOR,
+        Mode::WSPR,
+    ];
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Error returned when a string does not name a known [`Mode`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownModeError(pub String);
+
+impl Display for UnknownModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` is not a recognized ADIF MODE value", self.0)
+    }
+}
+
+impl FromStr for Mode {
+    type Err = UnknownModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Mode::ALL
+            .iter()
+            .copied()
+            .find(|mode| mode.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownModeError(s.to_string()))
+    }
+}
+
+/// Returns whether `code` falls within the range of assigned DXCC entity
+/// numbers. This is a structural check, not a lookup against the full ~400
+/// entry DXCC entity table
+///
+/// See: https://www.adif.org/312/ADIF_312.htm#DXCC_Entity_Code_Enumeration
+pub fn is_valid_dxcc_entity_code(code: i64) -> bool {
+    (1..=999).contains(&code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_award_from_str_case_insensitive() {
+        assert_eq!("was".parse::<Award>().unwrap(), Award::WAS);
+        assert_eq!("Was".parse::<Award>().unwrap(), Award::WAS);
+    }
+
+    #[test]
+    pub fn test_award_from_str_every_variant_parses_its_own_display() {
+        // A variant whose `Display` contains lowercase characters (like
+        // `CQWAZ_160m`) must still be able to parse its own rendering
+        for award in Award::ALL {
+            assert_eq!(award.to_string().parse::<Award>().unwrap(), *award);
+        }
+    }
+
+    #[test]
+    pub fn test_band_from_str() {
+        assert_eq!("40m".parse::<Band>().unwrap(), Band::Band40m);
+        assert_eq!("1.25M".parse::<Band>().unwrap(), Band::Band1_25m);
+        assert!("42m".parse::<Band>().is_err());
+    }
+
+    #[test]
+    pub fn test_band_every_variant_parses_its_own_display() {
+        for (band, _) in Band::ALL {
+            assert_eq!(band.to_string().parse::<Band>().unwrap(), *band);
+        }
+    }
+
+    #[test]
+    pub fn test_mode_from_str() {
+        assert_eq!("cw".parse::<Mode>().unwrap(), Mode::CW);
+        assert!("NOTAMODE".parse::<Mode>().is_err());
+    }
+}
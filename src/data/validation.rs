@@ -0,0 +1,164 @@
+use std::fmt::Display;
+
+use super::enums::{self, Award, Band, Mode};
+use super::{AdifRecord, SerializeError};
+
+/// A single enumeration-backed field that failed validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl AdifRecord {
+    /// Walk the enumeration-backed fields of this record (`BAND`, `MODE`,
+    /// `DXCC`, `AWARD`) and report every value that isn't one of the
+    /// enumeration members this crate knows. `MODE` is checked against
+    /// [`Mode`], which holds only a subset of ADIF's modes, and `DXCC` is
+    /// only checked to fall within `1..=999`
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        if let Some(band) = self.get("BAND").and_then(|val| val.as_str()) {
+            if band.parse::<Band>().is_err() {
+                errors.push(ValidationError {
+                    field: "BAND".to_string(),
+                    message: format!("`{}` is not a recognized ADIF band", band),
+                });
+            }
+        }
+
+        if let Some(mode) = self.get("MODE").and_then(|val| val.as_str()) {
+            if mode.parse::<Mode>().is_err() {
+                errors.push(ValidationError {
+                    field: "MODE".to_string(),
+                    message: format!("`{}` is not a recognized ADIF mode", mode),
+                });
+            }
+        }
+
+        if let Some(dxcc) = self.dxcc() {
+            if !enums::is_valid_dxcc_entity_code(dxcc) {
+                errors.push(ValidationError {
+                    field: "DXCC".to_string(),
+                    message: format!("`{}` is not a valid DXCC entity code", dxcc),
+                });
+            }
+        }
+
+        if let Some(award) = self.get("AWARD").and_then(|val| val.as_str()) {
+            if award.parse::<Award>().is_err() {
+                errors.push(ValidationError {
+                    field: "AWARD".to_string(),
+                    message: format!("`{}` is not a recognized ADIF award", award),
+                });
+            }
+        }
+
+        errors
+    }
+
+    /// The record's `BAND` field, parsed into a [`Band`]
+    pub fn band(&self) -> Option<Band> {
+        self.get("BAND").and_then(|val| val.as_str()?.parse().ok())
+    }
+
+    /// The record's `MODE` field, parsed into a [`Mode`]
+    pub fn mode(&self) -> Option<Mode> {
+        self.get("MODE").and_then(|val| val.as_str()?.parse().ok())
+    }
+
+    /// The record's `DXCC` entity code, if present and numeric
+    pub fn dxcc(&self) -> Option<i64> {
+        match self.get("DXCC")? {
+            super::AdifType::Integer(val) => Some(*val),
+            super::AdifType::Number(val) => Some(*val as i64),
+            other => other.as_str().and_then(|val| val.parse().ok()),
+        }
+    }
+
+    /// The record's `AWARD` field, parsed into an [`Award`]
+    pub fn award(&self) -> Option<Award> {
+        self.get("AWARD").and_then(|val| val.as_str()?.parse().ok())
+    }
+
+    /// Serialize into a full record string, first validating the
+    /// enumeration-backed fields the same way [`super::AdifType::Date`]
+    /// already rejects years before 1930. Prefer [`AdifRecord::serialize`]
+    /// if invalid enum values shouldn't block output
+    pub fn serialize_validated(&self) -> Result<String, SerializeError> {
+        if let Some(error) = self.validate().into_iter().next() {
+            let offender = match error.field.as_str() {
+                "DXCC" => self.dxcc().map(|code| code.to_string()),
+                field => self
+                    .get(field)
+                    .and_then(|val| val.as_str())
+                    .map(str::to_string),
+            }
+            .unwrap_or_default();
+            return Err(SerializeError {
+                message: error.message,
+                offender,
+            });
+        }
+
+        self.serialize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::super::AdifType;
+    use super::*;
+
+    #[test]
+    pub fn test_validate_band() {
+        let record: AdifRecord = indexmap! {
+            "BAND" => AdifType::Str("40m".to_string()),
+        }
+        .into();
+        assert!(record.validate().is_empty());
+
+        let record: AdifRecord = indexmap! {
+            "BAND" => AdifType::Str("42m".to_string()),
+        }
+        .into();
+        assert_eq!(record.validate().len(), 1);
+    }
+
+    #[test]
+    pub fn test_validate_award() {
+        let record: AdifRecord = indexmap! {
+            "AWARD" => AdifType::Str("WAS".to_string()),
+        }
+        .into();
+        assert!(record.validate().is_empty());
+        assert_eq!(record.award(), Some(Award::WAS));
+    }
+
+    #[test]
+    pub fn test_typed_band_and_mode_accessors() {
+        let record: AdifRecord = indexmap! {
+            "BAND" => AdifType::Str("40m".to_string()),
+            "MODE" => AdifType::Str("CW".to_string()),
+        }
+        .into();
+
+        assert_eq!(record.band(), Some(Band::Band40m));
+        assert_eq!(record.mode(), Some(Mode::CW));
+
+        let record: AdifRecord = indexmap! {
+            "BAND" => AdifType::Str("42m".to_string()),
+        }
+        .into();
+        assert_eq!(record.band(), None);
+    }
+}
@@ -3,5 +3,7 @@
 pub mod data;
 pub mod parser;
 
-pub use data::{AdifFile, AdifHeader, AdifRecord, AdifType};
-pub use parser::parse_adif;
+pub use data::{
+    AdifFile, AdifHeader, AdifRecord, AdifType, Award, Band, Mode, TimePrecision, ValidationError,
+};
+pub use parser::{parse_adif, parse_adif_lossy, ParseError, Span};
@@ -1,106 +1,368 @@
-use chrono::{Date, NaiveDate, NaiveTime, Utc};
+use chrono::{Datelike, NaiveDate, NaiveTime};
 use indexmap::IndexMap;
 use regex::Regex;
 
-use crate::data::{AdifFile, AdifHeader, AdifRecord, AdifType};
+use crate::data::{AdifFile, AdifHeader, AdifType, TimePrecision};
+#[cfg(test)]
+use crate::data::AdifRecord;
+
+const TOKEN_HEADER_RE: &str = r"<([A-Za-z_]+):(\d+)(?::([A-Za-z]))?>";
 
-const TOKEN_RE: &str = r"(?:<([A-Za-z_]+):(\d+)(?::([A-Za-z]))?>([^<]*))";
+/// A byte range into the source text a [`Token`] or [`ParseError`] was
+/// captured from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Describes why a chunk of ADIF source text could not be parsed, along
+/// with the [`Span`] of the offending bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (at bytes {}..{})",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Token {
     pub key: String,
-    pub len: usize,
     pub ty: Option<char>,
     pub value: String,
+    pub span: Span,
 }
 
-fn parse_line_to_tokens(line: &str) -> Vec<Token> {
-    Regex::new(TOKEN_RE)
-        .unwrap()
-        .captures_iter(line)
-        .map(|cap| Token {
-            key: cap[1].to_string().to_uppercase(),
-            len: cap[2].parse().expect("Length is not an integer"),
-            ty: match cap.get(3) {
-                Some(val) => Some(val.as_str().chars().next().unwrap().to_ascii_uppercase()),
-                None => None,
-            },
-            value: cap[4].trim_end().to_string(),
-        })
-        .collect()
+/// Returns the byte offset of `sub` within `base`, assuming `sub` is a
+/// slice taken from `base` (e.g. via `str::split`)
+fn byte_offset(base: &str, sub: &str) -> usize {
+    sub.as_ptr() as usize - base.as_ptr() as usize
 }
 
-fn create_token_map(tokens: Vec<Token>) -> IndexMap<String, AdifType> {
-    // Build a map
-    let mut map = IndexMap::new();
+/// Split `line` into its raw `<KEY:LEN[:TYPE]>VALUE` tokens. Each field's
+/// declared length is used to find the exact end of its value, rather than
+/// scanning for the next `<`, so that values may safely contain embedded
+/// `<` characters. `base_offset` is added to every reported [`Span`] so
+/// errors can point at the right place in the original, un-split source.
+fn parse_line_to_tokens(line: &str, base_offset: usize) -> Result<Vec<Token>, ParseError> {
+    let re = Regex::new(TOKEN_HEADER_RE).unwrap();
+    let mut tokens = Vec::new();
+    let mut cursor = 0;
 
-    // Handle every token
-    for token in tokens {
-        map.insert(
-            token.key.clone(),
-            match token.ty {
-                Some(ty) => match ty {
-                    'B' => AdifType::Boolean(token.value.to_uppercase() == "Y"),
-                    'N' => AdifType::Number(
-                        lexical::parse(token.value.to_string())
-                            .expect("Found a number value that cannot be parsed"),
-                    ),
-                    'D' => AdifType::Date(Date::from_utc(
-                        NaiveDate::parse_from_str(token.value.as_str(), "%Y%m%d").unwrap(),
-                        Utc,
-                    )),
-                    'T' => AdifType::Time(
-                        NaiveTime::parse_from_str(token.value.as_str(), "%H%M%S").unwrap(),
-                    ),
-                    _ => AdifType::Str(token.value),
+    while let Some(cap) = re.captures_at(line, cursor) {
+        let header = cap.get(0).unwrap();
+        let len_match = cap.get(2).unwrap();
+        let len: usize = len_match.as_str().parse().map_err(|_| ParseError {
+            message: "Field length is not a valid integer".to_string(),
+            span: Span {
+                start: base_offset + len_match.start(),
+                end: base_offset + len_match.end(),
+            },
+        })?;
+
+        let value_start = header.end();
+        let value_end = value_start + len;
+        if value_end > line.len() {
+            return Err(ParseError {
+                message: format!(
+                    "Field `{}` declares a length of {} bytes, but only {} are available",
+                    &cap[1],
+                    len,
+                    line.len() - value_start
+                ),
+                span: Span {
+                    start: base_offset + header.start(),
+                    end: base_offset + line.len(),
+                },
+            });
+        }
+        if !line.is_char_boundary(value_end) {
+            return Err(ParseError {
+                message: format!(
+                    "Field `{}` declares a length of {} bytes, but that splits a multi-byte UTF-8 character",
+                    &cap[1], len
+                ),
+                span: Span {
+                    start: base_offset + header.start(),
+                    end: base_offset + value_end,
+                },
+            });
+        }
+
+        // Whitespace between fields is common in hand-formatted ADIF files,
+        // but anything else trailing the declared length before the next
+        // field means the length was wrong
+        let trailing = line[value_end..].trim_start();
+        if !trailing.is_empty() && !trailing.starts_with('<') {
+            return Err(ParseError {
+                message: format!(
+                    "Field `{}` declares a length of {} bytes, but its value is not {} bytes long",
+                    &cap[1], len, len
+                ),
+                span: Span {
+                    start: base_offset + header.start(),
+                    end: base_offset + value_end,
                 },
-                None => AdifType::Str(token.value),
+            });
+        }
+
+        tokens.push(Token {
+            key: cap[1].to_uppercase(),
+            ty: cap
+                .get(3)
+                .map(|m| m.as_str().chars().next().unwrap().to_ascii_uppercase()),
+            value: line[value_start..value_end].to_string(),
+            span: Span {
+                start: base_offset + header.start(),
+                end: base_offset + value_end,
             },
-        );
+        });
+
+        cursor = value_end;
+    }
+
+    Ok(tokens)
+}
+
+/// Like [`parse_line_to_tokens`], but recovers from malformed field headers
+/// instead of failing: a field whose declared length runs past the end of
+/// the line is clamped to whatever data remains, and a field whose length
+/// isn't a valid integer is skipped entirely.
+fn parse_line_to_tokens_lossy(line: &str, base_offset: usize) -> Vec<Token> {
+    let re = Regex::new(TOKEN_HEADER_RE).unwrap();
+    let mut tokens = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(cap) = re.captures_at(line, cursor) {
+        let header = cap.get(0).unwrap();
+        let len_match = cap.get(2).unwrap();
+
+        let len: usize = match len_match.as_str().parse() {
+            Ok(len) => len,
+            Err(_) => {
+                cursor = header.end();
+                continue;
+            }
+        };
+
+        let value_start = header.end();
+        // Clamp to a char boundary too, not just the end of the line --
+        // a declared length that splits a multi-byte UTF-8 character must
+        // recover by shrinking the value rather than panicking on the slice
+        let value_end = floor_char_boundary(line, (value_start + len).min(line.len()));
+
+        tokens.push(Token {
+            key: cap[1].to_uppercase(),
+            ty: cap
+                .get(3)
+                .map(|m| m.as_str().chars().next().unwrap().to_ascii_uppercase()),
+            value: line[value_start..value_end].to_string(),
+            span: Span {
+                start: base_offset + header.start(),
+                end: base_offset + value_end,
+            },
+        });
+
+        cursor = value_end;
+    }
+
+    tokens
+}
+
+/// Find the largest char boundary in `s` that is `<= index`
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while index > 0 && !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Parse a UTC time in either the spec-legal 6-digit `HHMMSS` form or the
+/// 4-digit `HHMM` form, trying the more precise form first so `230300`
+/// isn't mistaken for an `HHMM` value with trailing garbage
+fn parse_time(value: &str) -> Option<(NaiveTime, TimePrecision)> {
+    if let Ok(time) = NaiveTime::parse_from_str(value, "%H%M%S") {
+        return Some((time, TimePrecision::HourMinuteSecond));
+    }
+
+    NaiveTime::parse_from_str(value, "%H%M")
+        .ok()
+        .map(|time| (time, TimePrecision::HourMinute))
+}
+
+/// Parse an ADIF `XDDD MM.MMM` location string (e.g. `N045 30.000` or
+/// `W122 30.000`) into signed decimal degrees. `positive`/`negative` are the
+/// two direction letters this axis may be written with (`N`/`S` for a
+/// latitude, `E`/`W` for a longitude); any other leading letter is rejected
+/// so a `LON` field written with `N`/`S` doesn't silently parse as a
+/// latitude
+fn parse_location(value: &str, positive: char, negative: char) -> Option<f64> {
+    let mut chars = value.chars();
+    let sign = match chars.next()? {
+        c if c == positive => 1.0,
+        c if c == negative => -1.0,
+        _ => return None,
+    };
+
+    let (degrees, minutes) = chars.as_str().split_once(' ')?;
+    let degrees: f64 = degrees.parse().ok()?;
+    let minutes: f64 = minutes.parse().ok()?;
+
+    Some(sign * (degrees + (minutes / 60.0)))
+}
+
+/// Convert a single token into its typed [`AdifType`] value
+fn convert_token(token: &Token) -> Result<AdifType, ParseError> {
+    match token.ty {
+        Some(ty) => match ty {
+            'B' => Ok(AdifType::Boolean(token.value.to_uppercase() == "Y")),
+            'N' => {
+                // Whole numbers take the `Integer` path so they never have
+                // to pass through `f64` (and risk losing precision) just to
+                // be read back out; anything with a fractional part or that
+                // doesn't fit an `i64` falls back to `Number`
+                if let Ok(val) = lexical::parse::<i64, _>(token.value.as_str()) {
+                    Ok(AdifType::Integer(val))
+                } else {
+                    lexical::parse(token.value.as_str())
+                        .map(AdifType::Number)
+                        .map_err(|_| ParseError {
+                            message: format!("`{}` is not a valid number", token.value),
+                            span: token.span,
+                        })
+                }
+            }
+            'D' => {
+                let date = NaiveDate::parse_from_str(token.value.as_str(), "%Y%m%d")
+                    .map_err(|_| ParseError {
+                        message: format!("`{}` is not a valid YYYYMMDD date", token.value),
+                        span: token.span,
+                    })?;
+
+                // Structurally valid dates before 1930 are still rejected,
+                // the same way `AdifType::Date::serialize` already does --
+                // otherwise parsing would hand back an `AdifFile` that's
+                // guaranteed to fail to serialize
+                if date.year() < 1930 {
+                    return Err(ParseError {
+                        message: format!(
+                            "`{}` is not a valid ADIF date (year must be >= 1930)",
+                            token.value
+                        ),
+                        span: token.span,
+                    });
+                }
+
+                Ok(AdifType::Date(date))
+            }
+            'T' => parse_time(token.value.as_str())
+                .map(|(time, precision)| AdifType::Time(time, precision))
+                .ok_or_else(|| ParseError {
+                    message: format!("`{}` is not a valid HHMMSS or HHMM time", token.value),
+                    span: token.span,
+                }),
+            'I' => Ok(AdifType::IntlString(token.value.clone())),
+            'M' => Ok(AdifType::MultilineString(token.value.clone())),
+            'G' => Ok(AdifType::IntlMultilineString(token.value.clone())),
+            'L' if token.key == "LON" => parse_location(token.value.as_str(), 'E', 'W')
+                .map(AdifType::Longitude)
+                .ok_or_else(|| ParseError {
+                    message: format!("`{}` is not a valid longitude", token.value),
+                    span: token.span,
+                }),
+            'L' => parse_location(token.value.as_str(), 'N', 'S')
+                .map(AdifType::Latitude)
+                .ok_or_else(|| ParseError {
+                    message: format!("`{}` is not a valid latitude", token.value),
+                    span: token.span,
+                }),
+            _ => Ok(AdifType::Str(token.value.clone())),
+        },
+        None => Ok(AdifType::Str(token.value.clone())),
     }
-    map
 }
 
-fn parse_tokens_to_record(tokens: Vec<Token>) -> AdifRecord {
-    create_token_map(tokens).into()
+fn create_token_map(tokens: &[Token]) -> Result<IndexMap<String, AdifType>, ParseError> {
+    let mut map = IndexMap::new();
+    for token in tokens {
+        map.insert(token.key.clone(), convert_token(token)?);
+    }
+    Ok(map)
 }
 
-fn parse_tokens_to_header(tokens: Vec<Token>) -> AdifHeader {
-    create_token_map(tokens).into()
+/// Like [`create_token_map`], but falls back to [`AdifType::Str`] for any
+/// token whose typed value fails to parse, instead of failing outright
+fn create_token_map_lossy(tokens: &[Token]) -> IndexMap<String, AdifType> {
+    tokens
+        .iter()
+        .map(|token| {
+            let value =
+                convert_token(token).unwrap_or_else(|_| AdifType::Str(token.value.clone()));
+            (token.key.clone(), value)
+        })
+        .collect()
 }
 
 /// Parse the contents of an ADIF (`.adi`) file into a struct representation
-pub fn parse_adif(data: &str) -> AdifFile {
-    // Clean up EOH and EOR tokens
+pub fn parse_adif(data: &str) -> Result<AdifFile, ParseError> {
+    let data = data.replace("<eoh>", "<EOH>").replace("<eor>", "<EOR>");
+    let parts: Vec<&str> = data.split("<EOH>").collect();
+
+    let header_raw = parts.first().copied().unwrap_or("");
+    let body_raw = parts.last().copied().unwrap_or("");
+    let body_offset = byte_offset(&data, body_raw);
+
+    let header_tokens = parse_line_to_tokens(header_raw, 0)?;
+    let header: AdifHeader = create_token_map(&header_tokens)?.into();
+
+    let mut body = Vec::new();
+    for record_line in body_raw.split("<EOR>") {
+        let record_offset = body_offset + byte_offset(body_raw, record_line);
+        let record_tokens = parse_line_to_tokens(record_line, record_offset)?;
+        body.push(create_token_map(&record_tokens)?.into());
+    }
+
+    Ok(AdifFile { header, body })
+}
+
+/// Like [`parse_adif`], but never fails: any field whose typed value can't
+/// be parsed (a malformed date, a non-numeric `N` field, and so on) falls
+/// back to a raw [`AdifType::Str`] instead of aborting the whole parse.
+/// This is the non-panicking replacement for the old behavior of
+/// `parse_adif` panicking on the first unparseable field.
+pub fn parse_adif_lossy(data: &str) -> AdifFile {
     let data = data.replace("<eoh>", "<EOH>").replace("<eor>", "<EOR>");
-    let data = data.split("<EOH>");
-    let data = data.collect::<Vec<&str>>();
+    let parts: Vec<&str> = data.split("<EOH>").collect();
 
-    // Split file into a header and body
-    let header_raw = data.first().unwrap_or(&"");
-    let body_raw = data.last().unwrap_or(&"");
+    let header_raw = parts.first().copied().unwrap_or("");
+    let body_raw = parts.last().copied().unwrap_or("");
+    let body_offset = byte_offset(&data, body_raw);
 
-    // Parse the header
-    let header_tokens = parse_line_to_tokens(&header_raw);
-    let header = parse_tokens_to_header(header_tokens);
+    let header_tokens = parse_line_to_tokens_lossy(header_raw, 0);
+    let header: AdifHeader = create_token_map_lossy(&header_tokens).into();
 
-    // Create the file
-    let file = AdifFile {
+    AdifFile {
         header,
         body: body_raw
             .split("<EOR>")
-            .collect::<Vec<&str>>()
-            .iter()
             .map(|record_line| {
-                // Parse the record
-                let record_tokens = parse_line_to_tokens(&record_line);
-                parse_tokens_to_record(record_tokens)
+                let record_offset = body_offset + byte_offset(body_raw, record_line);
+                let record_tokens = parse_line_to_tokens_lossy(record_line, record_offset);
+                create_token_map_lossy(&record_tokens).into()
             })
             .collect(),
-    };
-
-    // Return
-    file
+    }
 }
 
 #[cfg(test)]
@@ -110,8 +372,10 @@ mod tokenization_tests {
     #[test]
     pub fn test_line_to_tokens() {
         let result = parse_line_to_tokens(
-            "<CALL:4>VA3ZZA <BAND:3>40m <MODE:2>CW <NAME:12>Evan Pratten <eor>",
-        );
+            "<CALL:6>VA3ZZA<BAND:3>40m<MODE:2>CW<NAME:12>Evan Pratten<eor>",
+            0,
+        )
+        .unwrap();
 
         assert_eq!(result.len(), 4);
         assert_eq!(result[0].key, "CALL");
@@ -120,16 +384,126 @@ mod tokenization_tests {
         assert_eq!(result[3].value, "Evan Pratten");
     }
 
+    #[test]
+    pub fn test_line_to_tokens_length_mismatch() {
+        // CALL declares 4 bytes, but "VA3ZZA" is 6
+        let err = parse_line_to_tokens("<CALL:4>VA3ZZA<eor>", 0).unwrap_err();
+        assert_eq!(err.span, Span { start: 0, end: 12 });
+    }
+
+    #[test]
+    pub fn test_line_to_tokens_char_boundary_mismatch_errors() {
+        // "É" is 2 UTF-8 bytes, so a declared length of 1 splits it rather
+        // than just being short by one byte
+        let err = parse_line_to_tokens("<NAME:1:I>É<eor>", 0).unwrap_err();
+        assert_eq!(err.span, Span { start: 0, end: 11 });
+    }
+
+    #[test]
+    pub fn test_line_to_tokens_lossy_char_boundary_mismatch_recovers() {
+        let result = parse_line_to_tokens_lossy("<NAME:1:I>É<eor>", 0);
+        assert_eq!(result[0].value, "");
+    }
+
     #[test]
     pub fn test_tokens_to_record() {
-        let tokens = parse_line_to_tokens("<CALL:4>VA3ZZA<A_NUMBER:3:N>401<BOOL:1:B>N<eor>");
-        let record = parse_tokens_to_record(tokens);
+        let tokens =
+            parse_line_to_tokens("<CALL:6>VA3ZZA<A_NUMBER:3:N>401<BOOL:1:B>N<eor>", 0).unwrap();
+        let record: AdifRecord = create_token_map(&tokens).unwrap().into();
 
         assert_eq!(
             record.get("CALL"),
             Some(&AdifType::Str("VA3ZZA".to_string()))
         );
-        assert_eq!(record.get("A_NUMBER"), Some(&AdifType::Number(401.0)));
+        assert_eq!(record.get("A_NUMBER"), Some(&AdifType::Integer(401)));
         assert_eq!(record.get("BOOL"), Some(&AdifType::Boolean(false)));
     }
+
+    #[test]
+    pub fn test_parse_n_field_fractional_falls_back_to_number() {
+        let tokens = parse_line_to_tokens("<FREQ:3:N>7.1<eor>", 0).unwrap();
+        let record: AdifRecord = create_token_map(&tokens).unwrap().into();
+
+        assert_eq!(record.get("FREQ"), Some(&AdifType::Number(7.1)));
+    }
+
+    #[test]
+    pub fn test_parse_n_field_large_integer_does_not_lose_precision() {
+        // 9007199254740993 is the first integer an f64 can't represent
+        // exactly; it must come back out exactly as it went in
+        let tokens = parse_line_to_tokens("<BIGNUM:16:N>9007199254740993<eor>", 0).unwrap();
+        let record: AdifRecord = create_token_map(&tokens).unwrap().into();
+
+        assert_eq!(
+            record.get("BIGNUM"),
+            Some(&AdifType::Integer(9007199254740993))
+        );
+    }
+
+    #[test]
+    pub fn test_parse_lon_round_trips_west() {
+        let tokens = parse_line_to_tokens("<LON:11:L>W122 30.000<eor>", 0).unwrap();
+        let record: AdifRecord = create_token_map(&tokens).unwrap().into();
+
+        assert_eq!(record.get("LON"), Some(&AdifType::Longitude(-122.5)));
+        assert_eq!(
+            record.get("LON").unwrap().serialize("LON").unwrap(),
+            "<LON:11:L>W122 30.000"
+        );
+    }
+
+    #[test]
+    pub fn test_parse_date_before_1930_rejected() {
+        let tokens = parse_line_to_tokens("<QSO_DATE:8:D>19200101<eor>", 0).unwrap();
+        let err = create_token_map(&tokens).unwrap_err();
+        assert_eq!(err.span, tokens[0].span);
+    }
+
+    #[test]
+    pub fn test_parse_time_hour_minute() {
+        let tokens = parse_line_to_tokens("<TIME_ON:4:T>2330<eor>", 0).unwrap();
+        let record: AdifRecord = create_token_map(&tokens).unwrap().into();
+
+        assert_eq!(
+            record.get("TIME_ON"),
+            Some(&AdifType::Time(
+                chrono::NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+                TimePrecision::HourMinute
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_parse_time_hour_minute_second() {
+        let tokens = parse_line_to_tokens("<TIME_ON:6:T>233005<eor>", 0).unwrap();
+        let record: AdifRecord = create_token_map(&tokens).unwrap().into();
+
+        assert_eq!(
+            record.get("TIME_ON"),
+            Some(&AdifType::Time(
+                chrono::NaiveTime::from_hms_opt(23, 30, 5).unwrap(),
+                TimePrecision::HourMinuteSecond
+            ))
+        );
+    }
+}
+
+#[cfg(test)]
+mod parse_adif_tests {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_adif_propagates_length_errors() {
+        let result = parse_adif("<EOH><CALL:4>VA3ZZA<eor>");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_parse_adif_lossy_recovers() {
+        let file = parse_adif_lossy("<EOH><CALL:4>VA3ZZA<eor>");
+        assert_eq!(
+            file.body[0].get("CALL"),
+            Some(&AdifType::Str("VA3Z".to_string()))
+        );
+    }
 }